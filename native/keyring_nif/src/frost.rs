@@ -0,0 +1,594 @@
+//! FROST (Flexible Round-Optimized Schnorr Threshold) signatures over Ed25519.
+//!
+//! Lets `t`-of-`n` nodes jointly produce a standard Ed25519 signature — one
+//! that any existing `ed25519_verify` call can check — without any single
+//! node ever holding the full signing key. Three phases, matching the
+//! rounds a coordinator drives over the mesh:
+//!
+//!   1. keygen round1/round2 — each participant deals Shamir shares of a
+//!      random polynomial and the group verifies them against published
+//!      commitments.
+//!   2. sign round1 — each signer publishes two nonce commitments.
+//!   3. sign round2 / aggregate — each signer computes its partial
+//!      response, the coordinator sums them into a single signature.
+//!
+//! State that crosses the Elixir boundary between rounds is passed as
+//! plain binaries (scalars and compressed points), never as a resource,
+//! since keygen/signing participants are expected to live on different
+//! BEAM nodes. The `*_core` functions hold all the actual math on plain
+//! byte slices, independent of any `rustler::Env`, so the protocol can be
+//! exercised directly in tests; the `#[rustler::nif]` functions are thin
+//! binary/map wrappers around them.
+//!
+//! (Protocol follows Serai's Schnorr threshold multisig design.)
+
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rustler::{Atom, Binary, Encoder, Env, NewBinary, Term};
+use sha2::{Digest, Sha512};
+
+mod atoms {
+    rustler::atoms! {
+        ok,
+        error,
+        invalid_share,
+        identity_element,
+        invalid_signature,
+        group_public,
+        secret_share,
+        public_share,
+    }
+}
+
+fn scalar_to_bytes(s: &Scalar) -> [u8; 32] {
+    s.to_bytes()
+}
+
+fn point_to_bytes(p: &EdwardsPoint) -> [u8; 32] {
+    p.compress().to_bytes()
+}
+
+fn scalar_from_bytes(b: &[u8]) -> Result<Scalar, Atom> {
+    if b.len() != 32 {
+        return Err(atoms::error());
+    }
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(b);
+    Option::from(Scalar::from_canonical_bytes(bytes)).ok_or_else(atoms::error)
+}
+
+fn point_from_bytes(b: &[u8]) -> Result<EdwardsPoint, Atom> {
+    if b.len() != 32 {
+        return Err(atoms::error());
+    }
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(b);
+    CompressedEdwardsY(bytes).decompress().ok_or_else(atoms::error)
+}
+
+/// Hashes arbitrary bytes down to a scalar via wide SHA-512 reduction, the
+/// same construction RFC 8032 uses for the Ed25519 challenge — required so
+/// the final signature verifies under plain `ed25519_verify`.
+fn hash_to_scalar(chunks: &[&[u8]]) -> Scalar {
+    let mut hasher = Sha512::new();
+    for chunk in chunks {
+        hasher.update(chunk);
+    }
+    let digest: [u8; 64] = hasher.finalize().into();
+    Scalar::from_bytes_mod_order_wide(&digest)
+}
+
+/// Binding factor `ρ_i = H(i, msg, B)` for signer `index` over the full
+/// commitment list `B`. Only used internally during signing, so it doesn't
+/// need to match RFC 8032 — any domain-separated hash-to-scalar will do.
+fn binding_factor(index: u16, message: &[u8], commitments: &[(u16, EdwardsPoint, EdwardsPoint)]) -> Scalar {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"frost-binding-factor");
+    hasher.update(&index.to_be_bytes());
+    hasher.update(message);
+    for (idx, d, e) in commitments {
+        hasher.update(&idx.to_be_bytes());
+        hasher.update(d.compress().as_bytes());
+        hasher.update(e.compress().as_bytes());
+    }
+    let mut wide = [0u8; 64];
+    hasher.finalize_xof().fill(&mut wide);
+    Scalar::from_bytes_mod_order_wide(&wide)
+}
+
+/// Lagrange coefficient for `index` over the active signer set `indices`.
+fn lagrange_coefficient(index: u16, indices: &[u16]) -> Scalar {
+    let i = Scalar::from(index as u64);
+    let mut num = Scalar::ONE;
+    let mut den = Scalar::ONE;
+    for &j in indices {
+        if j == index {
+            continue;
+        }
+        let j = Scalar::from(j as u64);
+        num *= j;
+        den *= j - i;
+    }
+    num * den.invert()
+}
+
+/// Evaluates a polynomial (given as point commitments to its coefficients)
+/// at `x`, i.e. computes `Σ commitments[k] · x^k`.
+fn evaluate_commitments(commitments: &[EdwardsPoint], x: u16) -> EdwardsPoint {
+    let x = Scalar::from(x as u64);
+    let mut x_pow = Scalar::ONE;
+    let mut acc = EdwardsPoint::default();
+    for c in commitments {
+        acc += c * x_pow;
+        x_pow *= x;
+    }
+    acc
+}
+
+/// `(secret_share, group_public, public_share)`, each a 32-byte scalar or
+/// compressed point, as returned by [`keygen_round2_core`].
+type KeygenRound2Shares = ([u8; 32], [u8; 32], [u8; 32]);
+
+fn parse_commitment_points(bytes: &[u8]) -> Result<Vec<EdwardsPoint>, Atom> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(32) {
+        return Err(atoms::error());
+    }
+    bytes.chunks(32).map(point_from_bytes).collect()
+}
+
+/// Keygen round 1 core: sample a degree-`(threshold - 1)` polynomial,
+/// return commitments to its coefficients and the Shamir shares to send to
+/// every participant `1..=num_participants` (including ourselves).
+fn keygen_round1_core(threshold: u16, num_participants: u16) -> Result<(Vec<u8>, Vec<u8>), Atom> {
+    if threshold == 0 || threshold > num_participants {
+        return Err(atoms::error());
+    }
+
+    let mut coeffs = Vec::with_capacity(threshold as usize);
+    for _ in 0..threshold {
+        let mut bytes = [0u8; 64];
+        OsRng.fill_bytes(&mut bytes);
+        coeffs.push(Scalar::from_bytes_mod_order_wide(&bytes));
+    }
+
+    let mut commitments_bytes = vec![0u8; 32 * threshold as usize];
+    for (k, c) in coeffs.iter().enumerate() {
+        let point = ED25519_BASEPOINT_TABLE * c;
+        commitments_bytes[k * 32..(k + 1) * 32].copy_from_slice(&point_to_bytes(&point));
+    }
+
+    let mut shares_bytes = vec![0u8; 32 * num_participants as usize];
+    for target in 1..=num_participants {
+        let x = Scalar::from(target as u64);
+        let mut x_pow = Scalar::ONE;
+        let mut share = Scalar::ZERO;
+        for c in &coeffs {
+            share += c * x_pow;
+            x_pow *= x;
+        }
+        let offset = (target - 1) as usize * 32;
+        shares_bytes[offset..offset + 32].copy_from_slice(&scalar_to_bytes(&share));
+    }
+
+    Ok((commitments_bytes, shares_bytes))
+}
+
+/// Keygen round 2 core: verify every share received against its sender's
+/// published commitments, then combine them into this participant's final
+/// signing share and the group's public key.
+///
+/// `commitments` and `shares_received` must be parallel lists, one entry
+/// per dealer (including ourselves), each `shares_received[j]` being the
+/// 32-byte scalar dealer `j` sent to `participant_index`.
+fn keygen_round2_core(
+    participant_index: u16,
+    commitments: &[Vec<u8>],
+    shares_received: &[Vec<u8>],
+) -> Result<KeygenRound2Shares, Atom> {
+    if commitments.is_empty() || commitments.len() != shares_received.len() {
+        return Err(atoms::error());
+    }
+
+    let mut secret_share = Scalar::ZERO;
+    let mut group_public = EdwardsPoint::default();
+
+    for (dealer_commitments, share) in commitments.iter().zip(shares_received.iter()) {
+        let points = parse_commitment_points(dealer_commitments)?;
+        let share_scalar = scalar_from_bytes(share)?;
+
+        let expected = evaluate_commitments(&points, participant_index);
+        if ED25519_BASEPOINT_TABLE * &share_scalar != expected {
+            return Err(atoms::invalid_share());
+        }
+
+        secret_share += share_scalar;
+        group_public += points[0];
+    }
+
+    let public_share = ED25519_BASEPOINT_TABLE * &secret_share;
+
+    Ok((scalar_to_bytes(&secret_share), point_to_bytes(&group_public), point_to_bytes(&public_share)))
+}
+
+/// Signing round 1 core: sample the per-signer nonce pair `(d_i, e_i)` and
+/// their commitments `(D_i, E_i)`.
+fn sign_round1_core() -> ([u8; 64], [u8; 64]) {
+    let mut d_bytes = [0u8; 64];
+    let mut e_bytes = [0u8; 64];
+    OsRng.fill_bytes(&mut d_bytes);
+    OsRng.fill_bytes(&mut e_bytes);
+    let d = Scalar::from_bytes_mod_order_wide(&d_bytes);
+    let e = Scalar::from_bytes_mod_order_wide(&e_bytes);
+
+    let big_d = ED25519_BASEPOINT_TABLE * &d;
+    let big_e = ED25519_BASEPOINT_TABLE * &e;
+
+    let mut nonces = [0u8; 64];
+    nonces[..32].copy_from_slice(&scalar_to_bytes(&d));
+    nonces[32..].copy_from_slice(&scalar_to_bytes(&e));
+
+    let mut commitments = [0u8; 64];
+    commitments[..32].copy_from_slice(&point_to_bytes(&big_d));
+    commitments[32..].copy_from_slice(&point_to_bytes(&big_e));
+
+    (nonces, commitments)
+}
+
+fn parse_commitment_list(signer_indices: &[u16], commitments: &[Vec<u8>]) -> Result<Vec<(u16, EdwardsPoint, EdwardsPoint)>, Atom> {
+    if signer_indices.len() != commitments.len() || signer_indices.is_empty() {
+        return Err(atoms::error());
+    }
+    signer_indices
+        .iter()
+        .zip(commitments.iter())
+        .map(|(&idx, bytes)| {
+            if bytes.len() != 64 {
+                return Err(atoms::error());
+            }
+            let d = point_from_bytes(&bytes[..32])?;
+            let e = point_from_bytes(&bytes[32..])?;
+            Ok((idx, d, e))
+        })
+        .collect()
+}
+
+fn group_commitment(parsed: &[(u16, EdwardsPoint, EdwardsPoint)], message: &[u8]) -> EdwardsPoint {
+    let mut r = EdwardsPoint::default();
+    for &(idx, d, e) in parsed {
+        let rho = binding_factor(idx, message, parsed);
+        r += d + e * rho;
+    }
+    r
+}
+
+/// Signing round 2 core: compute this signer's partial response `z_i` and
+/// self-check it before it's published, so a bad nonce or share is caught
+/// at the source rather than silently corrupting the aggregate.
+#[allow(clippy::too_many_arguments)]
+fn sign_round2_core(
+    participant_index: u16,
+    secret_share: &[u8],
+    public_share: &[u8],
+    nonces: &[u8],
+    group_public: &[u8],
+    message: &[u8],
+    signer_indices: &[u16],
+    commitments: &[Vec<u8>],
+) -> Result<[u8; 32], Atom> {
+    if nonces.len() != 64 {
+        return Err(atoms::error());
+    }
+    let d = scalar_from_bytes(&nonces[..32])?;
+    let e = scalar_from_bytes(&nonces[32..])?;
+
+    let s_i = scalar_from_bytes(secret_share)?;
+    let y_i = point_from_bytes(public_share)?;
+    let y = point_from_bytes(group_public)?;
+
+    let parsed = parse_commitment_list(signer_indices, commitments)?;
+    let r = group_commitment(&parsed, message);
+    if r == EdwardsPoint::default() {
+        return Err(atoms::identity_element());
+    }
+    let c = hash_to_scalar(&[r.compress().as_bytes(), y.compress().as_bytes(), message]);
+
+    let rho_i = binding_factor(participant_index, message, &parsed);
+    let lambda_i = lagrange_coefficient(participant_index, signer_indices);
+    let z_i = d + e * rho_i + lambda_i * s_i * c;
+
+    let (_, my_d, my_e) = parsed
+        .iter()
+        .find(|(idx, _, _)| *idx == participant_index)
+        .copied()
+        .ok_or_else(atoms::error)?;
+    let expected = my_d + my_e * rho_i + (y_i * (lambda_i * c));
+    if ED25519_BASEPOINT_TABLE * &z_i != expected {
+        return Err(atoms::invalid_share());
+    }
+
+    Ok(scalar_to_bytes(&z_i))
+}
+
+/// Aggregation core: verifies every signer's partial response against its
+/// own public share before summing them into the final 64-byte Ed25519
+/// signature `(R, z)`, re-deriving `R` and `c` itself rather than trusting
+/// the coordinator. Rejects the result if `R` is the identity, if any
+/// `partial_responses[i]` fails its per-signer check (attributing the
+/// failure to that signer rather than the aggregate as a whole), or if the
+/// assembled signature doesn't verify against `group_public`.
+fn aggregate_core(
+    group_public: &[u8],
+    message: &[u8],
+    signer_indices: &[u16],
+    commitments: &[Vec<u8>],
+    public_shares: &[Vec<u8>],
+    partial_responses: &[Vec<u8>],
+) -> Result<[u8; 64], Atom> {
+    if signer_indices.len() != partial_responses.len() || signer_indices.len() != public_shares.len() {
+        return Err(atoms::error());
+    }
+
+    let y = point_from_bytes(group_public)?;
+    let parsed = parse_commitment_list(signer_indices, commitments)?;
+    let r = group_commitment(&parsed, message);
+    if r == EdwardsPoint::default() {
+        return Err(atoms::identity_element());
+    }
+    let c = hash_to_scalar(&[r.compress().as_bytes(), y.compress().as_bytes(), message]);
+
+    let mut z = Scalar::ZERO;
+    for (i, &participant_index) in signer_indices.iter().enumerate() {
+        let z_i = scalar_from_bytes(&partial_responses[i])?;
+        let y_i = point_from_bytes(&public_shares[i])?;
+
+        let rho_i = binding_factor(participant_index, message, &parsed);
+        let lambda_i = lagrange_coefficient(participant_index, signer_indices);
+        let (_, my_d, my_e) = parsed
+            .iter()
+            .find(|(idx, _, _)| *idx == participant_index)
+            .copied()
+            .ok_or_else(atoms::error)?;
+        let expected = my_d + my_e * rho_i + (y_i * (lambda_i * c));
+        if ED25519_BASEPOINT_TABLE * &z_i != expected {
+            return Err(atoms::invalid_share());
+        }
+
+        z += z_i;
+    }
+
+    let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&point_to_bytes(&y)).map_err(|_| atoms::error())?;
+    let mut sig_bytes = [0u8; 64];
+    sig_bytes[..32].copy_from_slice(&point_to_bytes(&r));
+    sig_bytes[32..].copy_from_slice(&scalar_to_bytes(&z));
+    let signature = ed25519_dalek::Signature::from_bytes(&sig_bytes);
+
+    use ed25519_dalek::Verifier;
+    if verifying_key.verify(message, &signature).is_err() {
+        return Err(atoms::invalid_signature());
+    }
+
+    Ok(sig_bytes)
+}
+
+fn binary_to_vec(b: &Binary) -> Vec<u8> {
+    b.as_slice().to_vec()
+}
+
+/// Keygen round 1: see [`keygen_round1_core`].
+///
+/// Returns `{commitments, shares}` where `commitments` is `threshold`
+/// concatenated 32-byte points and `shares` is `num_participants`
+/// concatenated 32-byte scalars, ordered by participant index `1..=n`.
+#[rustler::nif]
+fn frost_keygen_round1<'a>(env: Env<'a>, threshold: u16, num_participants: u16) -> Result<(Binary<'a>, Binary<'a>), Atom> {
+    let (commitments, shares) = keygen_round1_core(threshold, num_participants)?;
+
+    let mut commitments_bin = NewBinary::new(env, commitments.len());
+    commitments_bin.as_mut_slice().copy_from_slice(&commitments);
+
+    let mut shares_bin = NewBinary::new(env, shares.len());
+    shares_bin.as_mut_slice().copy_from_slice(&shares);
+
+    Ok((commitments_bin.into(), shares_bin.into()))
+}
+
+/// Keygen round 2: see [`keygen_round2_core`].
+#[rustler::nif]
+fn frost_keygen_round2<'a>(
+    env: Env<'a>,
+    participant_index: u16,
+    commitments: Vec<Binary<'a>>,
+    shares_received: Vec<Binary<'a>>,
+) -> Result<Term<'a>, Atom> {
+    let commitments: Vec<Vec<u8>> = commitments.iter().map(binary_to_vec).collect();
+    let shares_received: Vec<Vec<u8>> = shares_received.iter().map(binary_to_vec).collect();
+
+    let (secret_share, group_public, public_share) = keygen_round2_core(participant_index, &commitments, &shares_received)?;
+
+    let to_binary = |env: Env<'a>, bytes: &[u8; 32]| {
+        let mut bin = NewBinary::new(env, 32);
+        bin.as_mut_slice().copy_from_slice(bytes);
+        Binary::from(bin).to_term(env)
+    };
+
+    let map = Term::map_new(env);
+    let map = map.map_put(atoms::secret_share().encode(env), to_binary(env, &secret_share)).unwrap();
+    let map = map.map_put(atoms::group_public().encode(env), to_binary(env, &group_public)).unwrap();
+    let map = map.map_put(atoms::public_share().encode(env), to_binary(env, &public_share)).unwrap();
+
+    Ok(map)
+}
+
+/// Signing round 1: see [`sign_round1_core`].
+///
+/// Returns `{nonces, commitments}`, each a 64-byte binary holding the two
+/// 32-byte scalars/points concatenated. `nonces` must be kept secret by the
+/// signer and fed back into `frost_sign_round2`.
+#[rustler::nif]
+fn frost_sign_round1<'a>(env: Env<'a>) -> (Binary<'a>, Binary<'a>) {
+    let (nonces, commitments) = sign_round1_core();
+
+    let mut nonces_bin = NewBinary::new(env, 64);
+    nonces_bin.as_mut_slice().copy_from_slice(&nonces);
+
+    let mut commitments_bin = NewBinary::new(env, 64);
+    commitments_bin.as_mut_slice().copy_from_slice(&commitments);
+
+    (nonces_bin.into(), commitments_bin.into())
+}
+
+/// Signing round 2: see [`sign_round2_core`].
+#[rustler::nif]
+#[allow(clippy::too_many_arguments)]
+fn frost_sign_round2<'a>(
+    env: Env<'a>,
+    participant_index: u16,
+    secret_share: Binary<'a>,
+    public_share: Binary<'a>,
+    nonces: Binary<'a>,
+    group_public: Binary<'a>,
+    message: Binary<'a>,
+    signer_indices: Vec<u16>,
+    commitments: Vec<Binary<'a>>,
+) -> Result<Binary<'a>, Atom> {
+    let commitments: Vec<Vec<u8>> = commitments.iter().map(binary_to_vec).collect();
+    let z_i = sign_round2_core(
+        participant_index,
+        secret_share.as_slice(),
+        public_share.as_slice(),
+        nonces.as_slice(),
+        group_public.as_slice(),
+        message.as_slice(),
+        &signer_indices,
+        &commitments,
+    )?;
+
+    let mut out = NewBinary::new(env, 32);
+    out.as_mut_slice().copy_from_slice(&z_i);
+    Ok(out.into())
+}
+
+/// Aggregation: see [`aggregate_core`].
+#[rustler::nif]
+#[allow(clippy::too_many_arguments)]
+fn frost_aggregate<'a>(
+    env: Env<'a>,
+    group_public: Binary<'a>,
+    message: Binary<'a>,
+    signer_indices: Vec<u16>,
+    commitments: Vec<Binary<'a>>,
+    public_shares: Vec<Binary<'a>>,
+    partial_responses: Vec<Binary<'a>>,
+) -> Result<Binary<'a>, Atom> {
+    let commitments: Vec<Vec<u8>> = commitments.iter().map(binary_to_vec).collect();
+    let public_shares: Vec<Vec<u8>> = public_shares.iter().map(binary_to_vec).collect();
+    let partial_responses: Vec<Vec<u8>> = partial_responses.iter().map(binary_to_vec).collect();
+
+    let signature = aggregate_core(
+        group_public.as_slice(),
+        message.as_slice(),
+        &signer_indices,
+        &commitments,
+        &public_shares,
+        &partial_responses,
+    )?;
+
+    let mut out = NewBinary::new(env, 64);
+    out.as_mut_slice().copy_from_slice(&signature);
+    Ok(out.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs a full 2-of-3 keygen → sign → aggregate round trip and checks
+    /// the result verifies as a standard Ed25519 signature, exactly as
+    /// `ed25519_verify` would check it.
+    #[test]
+    fn threshold_sign_round_trip_verifies() {
+        const THRESHOLD: u16 = 2;
+        const PARTICIPANTS: u16 = 3;
+        let message = b"frost round-trip test message";
+
+        // Keygen round 1: every participant deals a polynomial.
+        let mut commitments = Vec::new();
+        let mut shares = Vec::new();
+        for _ in 1..=PARTICIPANTS {
+            let (c, s) = keygen_round1_core(THRESHOLD, PARTICIPANTS).unwrap();
+            commitments.push(c);
+            shares.push(s);
+        }
+
+        // Keygen round 2: each participant combines the shares addressed
+        // to it from every dealer.
+        let mut secret_shares = Vec::new();
+        let mut public_shares = Vec::new();
+        let mut group_public = None;
+        for i in 1..=PARTICIPANTS {
+            let shares_received: Vec<Vec<u8>> = shares
+                .iter()
+                .map(|dealer_shares| dealer_shares[(i as usize - 1) * 32..i as usize * 32].to_vec())
+                .collect();
+            let (secret_share, gp, public_share) = keygen_round2_core(i, &commitments, &shares_received).unwrap();
+            secret_shares.push(secret_share);
+            public_shares.push(public_share);
+            if let Some(existing) = group_public {
+                assert_eq!(existing, gp, "every participant must agree on the group public key");
+            }
+            group_public = Some(gp);
+        }
+        let group_public = group_public.unwrap();
+
+        // Sign with a 2-of-3 subset: participants 1 and 3.
+        let signer_indices = [1u16, 3u16];
+        let mut nonces = Vec::new();
+        let mut sign_commitments = Vec::new();
+        for _ in &signer_indices {
+            let (n, c) = sign_round1_core();
+            nonces.push(n);
+            sign_commitments.push(c.to_vec());
+        }
+
+        let partial_responses: Vec<Vec<u8>> = signer_indices
+            .iter()
+            .enumerate()
+            .map(|(pos, &idx)| {
+                sign_round2_core(
+                    idx,
+                    &secret_shares[idx as usize - 1],
+                    &public_shares[idx as usize - 1],
+                    &nonces[pos],
+                    &group_public,
+                    message,
+                    &signer_indices,
+                    &sign_commitments,
+                )
+                .unwrap()
+                .to_vec()
+            })
+            .collect();
+
+        let signer_public_shares: Vec<Vec<u8>> = signer_indices
+            .iter()
+            .map(|&idx| public_shares[idx as usize - 1].to_vec())
+            .collect();
+        let signature = aggregate_core(
+            &group_public,
+            message,
+            &signer_indices,
+            &sign_commitments,
+            &signer_public_shares,
+            &partial_responses,
+        )
+        .unwrap();
+
+        let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&group_public).unwrap();
+        let sig = ed25519_dalek::Signature::from_bytes(&signature);
+        use ed25519_dalek::Verifier;
+        assert!(verifying_key.verify(message, &sig).is_ok());
+    }
+}
@@ -0,0 +1,320 @@
+//! QUIC transport over quinn, with node identity bound to the node's
+//! Ed25519 keypair.
+//!
+//! Each endpoint's TLS certificate is a self-signed cert built from the
+//! same `SigningKey` `generate_keypair` produces, so a peer's verified
+//! certificate public key equals its `node_id` preimage. `quic_connect`
+//! pins the expected remote `node_id` and fails the handshake if the
+//! presented key doesn't hash to it; `quic_listen`/`quic_accept` accept any
+//! client whose certificate is well-formed and self-consistent (peer
+//! pinning on the server side is a matter for the mesh's membership layer,
+//! not the transport).
+//!
+//! All async work runs on a single background Tokio runtime shared by
+//! every endpoint in the process; NIFs block on it from a dirty scheduler
+//! so BEAM schedulers are never tied up waiting on the network.
+
+use ed25519_dalek::pkcs8::EncodePrivateKey;
+use ed25519_dalek::SigningKey;
+use rustler::{Atom, Binary, Env, NewBinary, ResourceArc};
+use std::sync::OnceLock;
+use std::time::Duration;
+use x509_parser::prelude::FromDer;
+
+mod atoms {
+    rustler::atoms! {
+        ok,
+        error,
+        timeout,
+        node_id_mismatch,
+        connection_closed,
+    }
+}
+
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()
+            .expect("failed to start QUIC background runtime")
+    })
+}
+
+// `quinn::Endpoint`/`quinn::Connection` are `Clone + Send + Sync` handles
+// meant to be used concurrently without external synchronization, but they
+// embed trait objects that aren't `RefUnwindSafe`, which rustler's
+// `ResourceArc` requires. Wrapping in a `Mutex` (as `store.rs` does for
+// `Database`) satisfies that bound for free, since `Mutex<T>` is
+// `RefUnwindSafe` regardless of `T`; every access locks just long enough to
+// clone the handle back out before doing any `.await`ing on it.
+pub struct QuicListener(std::sync::Mutex<quinn::Endpoint>);
+
+#[rustler::resource_impl]
+impl rustler::Resource for QuicListener {}
+
+pub struct QuicConnection(std::sync::Mutex<quinn::Connection>);
+
+#[rustler::resource_impl]
+impl rustler::Resource for QuicConnection {}
+
+/// Builds a self-signed end-entity certificate whose subject public key is
+/// `signing_key`'s, so the cert and the mesh node identity are one and the
+/// same key.
+fn self_signed_identity(signing_key: &SigningKey) -> Result<(rustls::pki_types::CertificateDer<'static>, rustls::pki_types::PrivateKeyDer<'static>), Atom> {
+    let pkcs8 = signing_key.to_pkcs8_der().map_err(|_| atoms::error())?;
+    let key_pair = rcgen::KeyPair::try_from(pkcs8.as_bytes()).map_err(|_| atoms::error())?;
+
+    let mut params = rcgen::CertificateParams::new(vec!["keyring-mesh-node".to_string()]).map_err(|_| atoms::error())?;
+    params.distinguished_name = rcgen::DistinguishedName::new();
+    let cert = params.self_signed(&key_pair).map_err(|_| atoms::error())?;
+
+    let cert_der = rustls::pki_types::CertificateDer::from(cert.der().to_vec());
+    let key_der = rustls::pki_types::PrivateKeyDer::try_from(pkcs8.as_bytes().to_vec()).map_err(|_| atoms::error())?;
+    Ok((cert_der, key_der))
+}
+
+/// Extracts the raw 32-byte Ed25519 public key from an end-entity cert's
+/// SubjectPublicKeyInfo. For Ed25519 (RFC 8410) the SPKI's
+/// `subjectPublicKey` BIT STRING payload *is* the raw 32-byte key, with no
+/// further ASN.1 wrapping, so once the cert is parsed there's nothing left
+/// to unwrap.
+fn extract_ed25519_public_key(cert: &rustls::pki_types::CertificateDer) -> Option<[u8; 32]> {
+    let (_, parsed) = x509_parser::certificate::X509Certificate::from_der(cert.as_ref()).ok()?;
+    let raw = parsed.public_key().subject_public_key.data.as_ref();
+    if raw.len() != 32 {
+        return None;
+    }
+    let mut key = [0u8; 32];
+    key.copy_from_slice(raw);
+    Some(key)
+}
+
+/// The signature-verification algorithm set used to check the
+/// `CertificateVerify` transcript signature against a peer's SPKI — shared
+/// by both verifiers below so the handshake actually proves possession of
+/// the private key behind the presented cert, not just its shape.
+fn verification_algorithms() -> &'static rustls::crypto::WebPkiSupportedAlgorithms {
+    static ALGORITHMS: OnceLock<rustls::crypto::WebPkiSupportedAlgorithms> = OnceLock::new();
+    ALGORITHMS.get_or_init(|| rustls::crypto::ring::default_provider().signature_verification_algorithms)
+}
+
+/// `rustls` server-cert verifier that pins the connection to a specific
+/// `node_id` — the BLAKE3 hash of the peer's Ed25519 public key.
+#[derive(Debug)]
+struct PinnedNodeVerifier {
+    expected_node_id: [u8; 32],
+}
+
+impl rustls::client::danger::ServerCertVerifier for PinnedNodeVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::pki_types::CertificateDer<'_>,
+        _intermediates: &[rustls::pki_types::CertificateDer<'_>],
+        _server_name: &rustls::pki_types::ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: rustls::pki_types::UnixTime,
+    ) -> Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        let public_key = extract_ed25519_public_key(end_entity)
+            .ok_or_else(|| rustls::Error::General("malformed Ed25519 certificate".into()))?;
+        let node_id = blake3::hash(&public_key);
+        if node_id.as_bytes() != &self.expected_node_id {
+            return Err(rustls::Error::General("node_id pin mismatch".into()));
+        }
+        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(&self, message: &[u8], cert: &rustls::pki_types::CertificateDer<'_>, dss: &rustls::DigitallySignedStruct) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, verification_algorithms())
+    }
+
+    fn verify_tls13_signature(&self, message: &[u8], cert: &rustls::pki_types::CertificateDer<'_>, dss: &rustls::DigitallySignedStruct) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, verification_algorithms())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        vec![rustls::SignatureScheme::ED25519]
+    }
+}
+
+/// Accepts any client cert without checking its issuer or its own
+/// signature; the handshake still checks the client's transcript signature
+/// against the cert's key. Used server-side, where membership/pinning is
+/// left to the mesh layer above the transport.
+#[derive(Debug)]
+struct AcceptAnyVerifier;
+
+impl rustls::server::danger::ClientCertVerifier for AcceptAnyVerifier {
+    fn offer_client_auth(&self) -> bool {
+        true
+    }
+
+    fn client_auth_mandatory(&self) -> bool {
+        true
+    }
+
+    fn verify_client_cert(
+        &self,
+        _end_entity: &rustls::pki_types::CertificateDer<'_>,
+        _intermediates: &[rustls::pki_types::CertificateDer<'_>],
+        _now: rustls::pki_types::UnixTime,
+    ) -> Result<rustls::server::danger::ClientCertVerified, rustls::Error> {
+        Ok(rustls::server::danger::ClientCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(&self, message: &[u8], cert: &rustls::pki_types::CertificateDer<'_>, dss: &rustls::DigitallySignedStruct) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, verification_algorithms())
+    }
+
+    fn verify_tls13_signature(&self, message: &[u8], cert: &rustls::pki_types::CertificateDer<'_>, dss: &rustls::DigitallySignedStruct) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, verification_algorithms())
+    }
+
+    fn root_hint_subjects(&self) -> &[rustls::DistinguishedName] {
+        &[]
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        vec![rustls::SignatureScheme::ED25519]
+    }
+}
+
+fn load_secret_key(secret_key: &Binary) -> Result<SigningKey, Atom> {
+    if secret_key.len() != 32 {
+        return Err(atoms::error());
+    }
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(secret_key.as_slice());
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+/// Starts listening on `port`, identified by the node's own keypair.
+#[rustler::nif(schedule = "DirtyIo")]
+fn quic_listen(port: u16, secret_key: Binary) -> Result<ResourceArc<QuicListener>, (Atom, Atom)> {
+    let signing_key = load_secret_key(&secret_key).map_err(|e| (atoms::error(), e))?;
+    let (cert, key) = self_signed_identity(&signing_key).map_err(|e| (atoms::error(), e))?;
+
+    let mut server_config = rustls::ServerConfig::builder()
+        .with_client_cert_verifier(std::sync::Arc::new(AcceptAnyVerifier))
+        .with_single_cert(vec![cert], key)
+        .map_err(|_| (atoms::error(), atoms::error()))?;
+    server_config.alpn_protocols = vec![b"keyring-mesh".to_vec()];
+
+    let quic_server_config = quinn::crypto::rustls::QuicServerConfig::try_from(server_config)
+        .map_err(|_| (atoms::error(), atoms::error()))?;
+    let server_config = quinn::ServerConfig::with_crypto(std::sync::Arc::new(quic_server_config));
+
+    let addr = format!("0.0.0.0:{port}").parse().map_err(|_| (atoms::error(), atoms::error()))?;
+    let endpoint = runtime()
+        .block_on(async { quinn::Endpoint::server(server_config, addr) })
+        .map_err(|_| (atoms::error(), atoms::error()))?;
+
+    Ok(ResourceArc::new(QuicListener(std::sync::Mutex::new(endpoint))))
+}
+
+/// Blocks until the next inbound connection completes its handshake.
+#[rustler::nif(schedule = "DirtyIo")]
+fn quic_accept(listener: ResourceArc<QuicListener>) -> Result<ResourceArc<QuicConnection>, (Atom, Atom)> {
+    let endpoint = listener.0.lock().map_err(|_| (atoms::error(), atoms::error()))?.clone();
+    let connection = runtime()
+        .block_on(async move {
+            let incoming = endpoint.accept().await.ok_or(())?;
+            let connecting = incoming.accept().map_err(|_| ())?;
+            connecting.await.map_err(|_| ())
+        })
+        .map_err(|_| (atoms::error(), atoms::connection_closed()))?;
+
+    Ok(ResourceArc::new(QuicConnection(std::sync::Mutex::new(connection))))
+}
+
+/// Dials `host:port`, pinning the handshake to `expected_node_id`.
+#[rustler::nif(schedule = "DirtyIo")]
+fn quic_connect(
+    host: String,
+    port: u16,
+    secret_key: Binary,
+    expected_node_id: Binary,
+) -> Result<ResourceArc<QuicConnection>, (Atom, Atom)> {
+    if expected_node_id.len() != 32 {
+        return Err((atoms::error(), atoms::error()));
+    }
+    let signing_key = load_secret_key(&secret_key).map_err(|e| (atoms::error(), e))?;
+    let (cert, key) = self_signed_identity(&signing_key).map_err(|e| (atoms::error(), e))?;
+
+    let mut expected = [0u8; 32];
+    expected.copy_from_slice(expected_node_id.as_slice());
+
+    let mut client_config = rustls::ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(std::sync::Arc::new(PinnedNodeVerifier { expected_node_id: expected }))
+        .with_client_auth_cert(vec![cert], key)
+        .map_err(|_| (atoms::error(), atoms::error()))?;
+    client_config.alpn_protocols = vec![b"keyring-mesh".to_vec()];
+
+    let quic_client_config = quinn::crypto::rustls::QuicClientConfig::try_from(client_config)
+        .map_err(|_| (atoms::error(), atoms::error()))?;
+
+    let connection = runtime()
+        .block_on(async move {
+            let remote_addr = tokio::net::lookup_host((host.as_str(), port))
+                .await
+                .map_err(|_| ())?
+                .next()
+                .ok_or(())?;
+
+            let mut endpoint = quinn::Endpoint::client("0.0.0.0:0".parse().unwrap()).map_err(|_| ())?;
+            endpoint.set_default_client_config(quinn::ClientConfig::new(std::sync::Arc::new(quic_client_config)));
+            let connecting = endpoint.connect(remote_addr, "keyring-mesh-node").map_err(|_| ())?;
+            connecting.await.map_err(|_| ())
+        })
+        .map_err(|_| (atoms::error(), atoms::node_id_mismatch()))?;
+
+    Ok(ResourceArc::new(QuicConnection(std::sync::Mutex::new(connection))))
+}
+
+/// Opens a bidirectional stream and writes `data` to it.
+#[rustler::nif(schedule = "DirtyIo")]
+fn quic_send(conn: ResourceArc<QuicConnection>, data: Binary) -> Result<Atom, (Atom, Atom)> {
+    let bytes = data.as_slice().to_vec();
+    let connection = conn.0.lock().map_err(|_| (atoms::error(), atoms::error()))?.clone();
+    runtime()
+        .block_on(async move {
+            let (mut send, _recv) = connection.open_bi().await.map_err(|_| ())?;
+            send.write_all(&bytes).await.map_err(|_| ())?;
+            send.finish().map_err(|_| ())
+        })
+        .map_err(|_| (atoms::error(), atoms::connection_closed()))?;
+    Ok(atoms::ok())
+}
+
+/// Accepts the next inbound stream and reads it to completion, honoring
+/// `timeout_ms`.
+#[rustler::nif(schedule = "DirtyIo")]
+fn quic_recv<'a>(env: Env<'a>, conn: ResourceArc<QuicConnection>, timeout_ms: u64) -> Result<Binary<'a>, (Atom, Atom)> {
+    let connection = conn.0.lock().map_err(|_| (atoms::error(), atoms::error()))?.clone();
+    let data = runtime()
+        .block_on(async move {
+            tokio::time::timeout(Duration::from_millis(timeout_ms), async {
+                let (_send, mut recv) = connection.accept_bi().await.map_err(|_| ())?;
+                recv.read_to_end(16 * 1024 * 1024).await.map_err(|_| ())
+            })
+            .await
+            .map_err(|_| atoms::timeout())
+        })
+        .map_err(|e| (atoms::error(), e))?
+        .map_err(|_| (atoms::error(), atoms::connection_closed()))?;
+
+    let mut out = NewBinary::new(env, data.len());
+    out.as_mut_slice().copy_from_slice(&data);
+    Ok(out.into())
+}
+
+/// Closes the connection.
+#[rustler::nif]
+fn quic_close(conn: ResourceArc<QuicConnection>) -> Atom {
+    if let Ok(connection) = conn.0.lock() {
+        connection.close(0u32.into(), b"closed");
+    }
+    atoms::ok()
+}
@@ -0,0 +1,171 @@
+//! redb-backed content-addressed store.
+//!
+//! Three tables in a single `redb::Database`: `blobs` keyed by their BLAKE3
+//! hash, `documents` keyed by document id, and a secondary index from
+//! `keyring_id` to document id so `store_list_documents` can range-scan a
+//! keyring's documents without a full table walk.
+
+use redb::{Database, MultimapTableDefinition, TableDefinition};
+use rustler::{Atom, Binary, Env, NewBinary, ResourceArc};
+use std::sync::Mutex;
+
+mod atoms {
+    rustler::atoms! {
+        ok,
+        error,
+        not_found,
+    }
+}
+
+const BLOBS: TableDefinition<&[u8], &[u8]> = TableDefinition::new("blobs");
+const DOCUMENTS: TableDefinition<&[u8], &[u8]> = TableDefinition::new("documents");
+const DOCUMENTS_BY_KEYRING: MultimapTableDefinition<&[u8], &[u8]> =
+    MultimapTableDefinition::new("documents_by_keyring");
+
+/// Handle to an opened store, shared with Elixir as a `ResourceArc`.
+pub struct Store(Mutex<Database>);
+
+#[rustler::resource_impl]
+impl rustler::Resource for Store {}
+
+/// Opens (creating if necessary) the redb database at `path`.
+#[rustler::nif(schedule = "DirtyIo")]
+fn store_open(path: String) -> Result<ResourceArc<Store>, (Atom, Atom)> {
+    let db = Database::create(&path).map_err(|_| (atoms::error(), atoms::error()))?;
+
+    // Make sure all three tables exist before we hand the handle back.
+    let txn = db.begin_write().map_err(|_| (atoms::error(), atoms::error()))?;
+    {
+        txn.open_table(BLOBS).map_err(|_| (atoms::error(), atoms::error()))?;
+        txn.open_table(DOCUMENTS).map_err(|_| (atoms::error(), atoms::error()))?;
+        txn.open_multimap_table(DOCUMENTS_BY_KEYRING)
+            .map_err(|_| (atoms::error(), atoms::error()))?;
+    }
+    txn.commit().map_err(|_| (atoms::error(), atoms::error()))?;
+
+    Ok(ResourceArc::new(Store(Mutex::new(db))))
+}
+
+/// Hashes `data` with BLAKE3 and writes it under that hash. Re-putting
+/// identical bytes is a no-op since the key is already content-addressed.
+#[rustler::nif(schedule = "DirtyIo")]
+fn store_put_blob<'a>(env: Env<'a>, store: ResourceArc<Store>, data: Binary<'a>) -> Result<(Atom, Binary<'a>), Atom> {
+    let hash = blake3::hash(data.as_slice());
+
+    let db = store.0.lock().map_err(|_| atoms::error())?;
+    let txn = db.begin_write().map_err(|_| atoms::error())?;
+    {
+        let mut table = txn.open_table(BLOBS).map_err(|_| atoms::error())?;
+        table
+            .insert(hash.as_bytes().as_slice(), data.as_slice())
+            .map_err(|_| atoms::error())?;
+    }
+    txn.commit().map_err(|_| atoms::error())?;
+
+    let mut out = NewBinary::new(env, 32);
+    out.as_mut_slice().copy_from_slice(hash.as_bytes());
+    Ok((atoms::ok(), out.into()))
+}
+
+/// Looks up a blob by its BLAKE3 hash.
+#[rustler::nif(schedule = "DirtyIo")]
+fn store_get_blob<'a>(env: Env<'a>, store: ResourceArc<Store>, hash: Binary<'a>) -> Result<(Atom, Binary<'a>), Atom> {
+    let db = store.0.lock().map_err(|_| atoms::error())?;
+    let txn = db.begin_read().map_err(|_| atoms::error())?;
+    let table = txn.open_table(BLOBS).map_err(|_| atoms::error())?;
+    let value = table
+        .get(hash.as_slice())
+        .map_err(|_| atoms::error())?
+        .ok_or_else(atoms::not_found)?;
+
+    let mut out = NewBinary::new(env, value.value().len());
+    out.as_mut_slice().copy_from_slice(value.value());
+    Ok((atoms::ok(), out.into()))
+}
+
+/// Whether a blob with the given hash is already stored.
+#[rustler::nif(schedule = "DirtyIo")]
+fn store_has_blob(store: ResourceArc<Store>, hash: Binary) -> Result<bool, Atom> {
+    let db = store.0.lock().map_err(|_| atoms::error())?;
+    let txn = db.begin_read().map_err(|_| atoms::error())?;
+    let table = txn.open_table(BLOBS).map_err(|_| atoms::error())?;
+    Ok(table.get(hash.as_slice()).map_err(|_| atoms::error())?.is_some())
+}
+
+/// Stores a document under `id`, indexing it by `keyring_id` so
+/// `store_list_documents` can find it later.
+#[rustler::nif(schedule = "DirtyIo")]
+fn store_put_document(store: ResourceArc<Store>, id: Binary, keyring_id: Binary, doc: Binary) -> Result<Atom, Atom> {
+    let db = store.0.lock().map_err(|_| atoms::error())?;
+    let txn = db.begin_write().map_err(|_| atoms::error())?;
+    {
+        let mut documents = txn.open_table(DOCUMENTS).map_err(|_| atoms::error())?;
+        documents
+            .insert(id.as_slice(), doc.as_slice())
+            .map_err(|_| atoms::error())?;
+
+        let mut by_keyring = txn
+            .open_multimap_table(DOCUMENTS_BY_KEYRING)
+            .map_err(|_| atoms::error())?;
+        by_keyring
+            .insert(keyring_id.as_slice(), id.as_slice())
+            .map_err(|_| atoms::error())?;
+    }
+    txn.commit().map_err(|_| atoms::error())?;
+    Ok(atoms::ok())
+}
+
+/// Looks up a document by id.
+#[rustler::nif(schedule = "DirtyIo")]
+fn store_get_document<'a>(env: Env<'a>, store: ResourceArc<Store>, id: Binary<'a>) -> Result<(Atom, Binary<'a>), Atom> {
+    let db = store.0.lock().map_err(|_| atoms::error())?;
+    let txn = db.begin_read().map_err(|_| atoms::error())?;
+    let table = txn.open_table(DOCUMENTS).map_err(|_| atoms::error())?;
+    let value = table
+        .get(id.as_slice())
+        .map_err(|_| atoms::error())?
+        .ok_or_else(atoms::not_found)?;
+
+    let mut out = NewBinary::new(env, value.value().len());
+    out.as_mut_slice().copy_from_slice(value.value());
+    Ok((atoms::ok(), out.into()))
+}
+
+/// Lists the ids of every document belonging to `keyring_id`.
+#[rustler::nif(schedule = "DirtyIo")]
+fn store_list_documents<'a>(env: Env<'a>, store: ResourceArc<Store>, keyring_id: Binary<'a>) -> Result<Vec<Binary<'a>>, Atom> {
+    let db = store.0.lock().map_err(|_| atoms::error())?;
+    let txn = db.begin_read().map_err(|_| atoms::error())?;
+    let by_keyring = txn
+        .open_multimap_table(DOCUMENTS_BY_KEYRING)
+        .map_err(|_| atoms::error())?;
+
+    let mut ids = Vec::new();
+    for entry in by_keyring.get(keyring_id.as_slice()).map_err(|_| atoms::error())? {
+        let id = entry.map_err(|_| atoms::error())?;
+        let mut out = NewBinary::new(env, id.value().len());
+        out.as_mut_slice().copy_from_slice(id.value());
+        ids.push(out.into());
+    }
+    Ok(ids)
+}
+
+/// Deletes a document by id, along with its entry in the keyring index.
+#[rustler::nif(schedule = "DirtyIo")]
+fn store_delete_document(store: ResourceArc<Store>, id: Binary, keyring_id: Binary) -> Result<Atom, Atom> {
+    let db = store.0.lock().map_err(|_| atoms::error())?;
+    let txn = db.begin_write().map_err(|_| atoms::error())?;
+    {
+        let mut documents = txn.open_table(DOCUMENTS).map_err(|_| atoms::error())?;
+        documents.remove(id.as_slice()).map_err(|_| atoms::error())?;
+
+        let mut by_keyring = txn
+            .open_multimap_table(DOCUMENTS_BY_KEYRING)
+            .map_err(|_| atoms::error())?;
+        by_keyring
+            .remove(keyring_id.as_slice(), id.as_slice())
+            .map_err(|_| atoms::error())?;
+    }
+    txn.commit().map_err(|_| atoms::error())?;
+    Ok(atoms::ok())
+}